@@ -35,7 +35,10 @@
  * if necessary.
  */
 
+use std::cast;
 use std::ptr;
+use std::unstable::raw::Slice;
+use std::vec::{VecIterator, VecMutIterator};
 
 /**
  * The type representing a foreign chunk of memory
@@ -135,6 +138,44 @@ impl <T> CVec<T> {
         }
     }
 
+    /**
+     * Returns an immutable slice over the contents of this `CVec`, built
+     * directly from the stored `base` pointer and `len`.
+     *
+     * This is the same borrowed-memory pattern as `get`: the returned slice
+     * is only valid for as long as the `CVec` is borrowed.
+     */
+    pub fn as_slice<'a>(&'a self) -> &'a [T] {
+        unsafe {
+            cast::transmute(Slice { data: self.base as *T, len: self.len })
+        }
+    }
+
+    /**
+     * Returns a mutable slice over the contents of this `CVec`, built
+     * directly from the stored `base` pointer and `len`.
+     */
+    pub fn as_mut_slice<'a>(&'a mut self) -> &'a mut [T] {
+        unsafe {
+            cast::transmute(Slice { data: self.base as *T, len: self.len })
+        }
+    }
+
+    /**
+     * Returns an iterator over references to the elements of the vector
+     */
+    pub fn iter<'a>(&'a self) -> VecIterator<'a, T> {
+        self.as_slice().iter()
+    }
+
+    /**
+     * Returns an iterator over mutable references to the elements of the
+     * vector
+     */
+    pub fn mut_iter<'a>(&'a mut self) -> VecMutIterator<'a, T> {
+        self.as_mut_slice().mut_iter()
+    }
+
     /**
      * Unwrap the pointer without running the destructor
      *
@@ -158,12 +199,23 @@ impl <T> Container for CVec<T> {
     fn len(&self) -> uint { self.len }
 }
 
+impl <T: Clone> Index<uint, T> for CVec<T> {
+    /// Retrieves an element at a given index, cloning it out.
+    ///
+    /// Fails if `ofs` is greater or equal to the length of the vector, the
+    /// same bounds check performed by `get`.
+    fn index(&self, ofs: &uint) -> T {
+        self.get(*ofs).clone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use std::libc::*;
     use std::libc;
+    use std::iter::AdditiveIterator;
     use std::ptr;
     use std::rt::global_heap::malloc_raw;
 
@@ -187,6 +239,39 @@ mod tests {
         assert_eq!(cv.len(), 16);
     }
 
+    #[test]
+    fn test_as_slice() {
+        let mut cv = malloc(16);
+
+        *cv.get_mut(3) = 8;
+        *cv.get_mut(4) = 9;
+        assert_eq!(cv.as_slice()[3], 8);
+        assert_eq!(cv.as_slice()[4], 9);
+        assert_eq!(cv.as_slice().len(), 16);
+
+        cv.as_mut_slice()[5] = 10;
+        assert_eq!(*cv.get(5), 10);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut cv = malloc(16);
+
+        *cv.get_mut(3) = 8;
+        assert_eq!(cv[3], 8);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut cv = malloc(4);
+        for (i, v) in cv.mut_iter().enumerate() {
+            *v = i as u8;
+        }
+
+        let total: uint = cv.iter().map(|&v| v as uint).sum();
+        assert_eq!(total, 0 + 1 + 2 + 3);
+    }
+
     #[test]
     #[should_fail]
     fn test_fail_at_null() {
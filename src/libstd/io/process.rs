@@ -16,6 +16,8 @@ use libc;
 use io;
 use io::IoResult;
 use rt::rtio::{RtioProcess, IoFactory, LocalIo};
+use comm::stream;
+use task;
 
 use fmt;
 
@@ -70,9 +72,15 @@ pub struct ProcessConfig<'a> {
     /// the same semantics as the `uid` field.
     gid: Option<uint>,
 
-    /// If true, the child process is spawned in a detached state. On unix, this
-    /// means that the child is the leader of a new process group.
+    /// If true, the child process is spawned in a detached state. On unix,
+    /// this means that the child is the leader of a new process group.
     detach: bool,
+
+    /// If true, the child process is spawned as the leader of a new process
+    /// group: `setsid()` on unix, `CREATE_NEW_PROCESS_GROUP` on windows.
+    /// This is what lets `signal_group` reach the whole tree of processes
+    /// the child spawns, rather than just the child itself.
+    create_group: bool,
 }
 
 /// Describes what to do with a standard io stream for a child process.
@@ -105,6 +113,17 @@ pub enum ProcessExit {
     ExitSignal(int),
 }
 
+/// The result of a process after it has exited and had its stdout/stderr
+/// fully drained, as returned by `Process::wait_with_output`.
+pub struct ProcessOutput {
+    /// The status that the process exited with
+    status: ProcessExit,
+    /// The data that the process wrote to stdout
+    output: ~[u8],
+    /// The data that the process wrote to stderr
+    error: ~[u8],
+}
+
 impl fmt::Show for ProcessExit {
     /// Format a ProcessExit enum, to nicely present the information.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -129,6 +148,121 @@ impl ProcessExit {
     }
 }
 
+/// The `Command` type acts as a builder for spawning a `Process`, wrapping
+/// the functional-update style of `ProcessConfig` in a set of chainable
+/// methods. This is generally the easiest way to configure and spawn a
+/// process.
+///
+/// ```rust
+/// use std::io::process::Command;
+///
+/// let output = Command::new("/bin/sh").arg("-c").arg("echo hello").spawn();
+/// ```
+pub struct Command {
+    priv program: ~str,
+    priv args: ~[~str],
+    priv env: Option<~[(~str, ~str)]>,
+    priv cwd: Option<~str>,
+    priv io: ~[StdioContainer],
+    priv uid: Option<uint>,
+    priv gid: Option<uint>,
+    priv detach: bool,
+    priv create_group: bool,
+}
+
+impl Command {
+    /// Creates a new `Command` for launching the program at path `program`,
+    /// with the following default configuration:
+    ///
+    /// * No arguments to the program
+    /// * Inherit the current process's environment
+    /// * Inherit the current process's working directory
+    /// * `stdin` is attached to `/dev/null` (ignored), while `stdout` and
+    ///   `stderr` are captured for use with `wait_with_output`
+    pub fn new(program: &str) -> Command {
+        Command {
+            program: program.to_owned(),
+            args: ~[],
+            env: None,
+            cwd: None,
+            io: ~[Ignored, CreatePipe(false, true), CreatePipe(false, true)],
+            uid: None,
+            gid: None,
+            detach: false,
+            create_group: false,
+        }
+    }
+
+    /// Adds an argument to pass to the program.
+    pub fn arg<'a>(&'a mut self, arg: &str) -> &'a mut Command {
+        self.args.push(arg.to_owned());
+        self
+    }
+
+    /// Adds multiple arguments to pass to the program.
+    pub fn args<'a>(&'a mut self, args: &[~str]) -> &'a mut Command {
+        self.args.push_all(args);
+        self
+    }
+
+    /// Sets the entire environment for the child process. If this is never
+    /// called, the child inherits the current process's environment.
+    pub fn env<'a>(&'a mut self, env: &[(~str, ~str)]) -> &'a mut Command {
+        self.env = Some(env.to_owned());
+        self
+    }
+
+    /// Sets the working directory for the child process.
+    pub fn cwd<'a>(&'a mut self, cwd: &str) -> &'a mut Command {
+        self.cwd = Some(cwd.to_owned());
+        self
+    }
+
+    /// Sets the `uid` to run the child process under. See
+    /// `ProcessConfig.uid` for more details.
+    pub fn uid<'a>(&'a mut self, id: uint) -> &'a mut Command {
+        self.uid = Some(id);
+        self
+    }
+
+    /// Sets the `gid` to run the child process under. See
+    /// `ProcessConfig.gid` for more details.
+    pub fn gid<'a>(&'a mut self, id: uint) -> &'a mut Command {
+        self.gid = Some(id);
+        self
+    }
+
+    /// Sets whether the child process is spawned in a detached state. See
+    /// `ProcessConfig.detach` for more details.
+    pub fn detach<'a>(&'a mut self, detach: bool) -> &'a mut Command {
+        self.detach = detach;
+        self
+    }
+
+    /// Sets whether the child process is spawned as the leader of a new
+    /// process group. See `ProcessConfig.create_group` for more details.
+    pub fn create_group<'a>(&'a mut self, create_group: bool) -> &'a mut Command {
+        self.create_group = create_group;
+        self
+    }
+
+    /// Spawns the configured program as a child process, returning the
+    /// handle to it on success.
+    pub fn spawn(&self) -> IoResult<Process> {
+        Process::new(ProcessConfig {
+            program: self.program.as_slice(),
+            args: self.args.as_slice(),
+            env: self.env.as_ref().map(|e| e.as_slice()),
+            cwd: self.cwd.as_ref().map(|c| c.as_slice()),
+            io: self.io.as_slice(),
+            uid: self.uid,
+            gid: self.gid,
+            detach: self.detach,
+            create_group: self.create_group,
+        })
+    }
+}
+
 impl<'a> ProcessConfig<'a> {
     /// Creates a new configuration with blanks as all of the defaults. This is
     /// useful when using functional struct updates:
@@ -155,6 +289,7 @@ impl<'a> ProcessConfig<'a> {
             uid: None,
             gid: None,
             detach: false,
+            create_group: false,
         }
     }
 }
@@ -190,10 +325,68 @@ impl Process {
         self.handle.kill(signal)
     }
 
+    /// Like `signal`, but delivered to the child's whole process group
+    /// (`kill(-pid, sig)` on unix) rather than just the child. Equivalent
+    /// to `signal` unless the child was spawned with `create_group`.
+    ///
+    /// If the signal delivery fails, the corresponding error is returned.
+    pub fn signal_group(&mut self, signal: int) -> IoResult<()> {
+        self.handle.kill_group(signal)
+    }
+
     /// Wait for the child to exit completely, returning the status that it
     /// exited with. This function will continue to have the same return value
     /// after it has been called at least once.
     pub fn wait(&mut self) -> ProcessExit { self.handle.wait() }
+
+    /// Wait for the child to exit, but only up to a given timeout.
+    ///
+    /// If the child exits before the specified number of milliseconds have
+    /// elapsed, then `Some` is returned with the exit status, otherwise
+    /// `None` is returned and the child may still be running.
+    ///
+    /// Note that this is purely a wrapper around libuv's timer functionality
+    /// for interrupting a currently blocking wait. If the timeout elapses
+    /// without the process exiting, the process is left to continue running,
+    /// and it is up to the caller to decide whether to `signal` it.
+    pub fn wait_timeout(&mut self, msecs: u64) -> Option<ProcessExit> {
+        self.handle.wait_timeout(msecs)
+    }
+
+    /// Simultaneously waits for the child to exit and collect all remaining
+    /// output on the stdout/stderr handles, returning a `ProcessOutput`.
+    ///
+    /// The stdout and stderr handles are read concurrently, one on a helper
+    /// task, so that a child which fills one pipe's buffer before the other
+    /// is drained cannot deadlock the parent.
+    pub fn wait_with_output(mut self) -> ProcessOutput {
+        // Close stdin first: a child that waits for its stdin to be closed
+        // before writing anything would otherwise hang this function
+        // forever, since nothing else signals EOF on it.
+        drop(self.io[0].take());
+
+        let stdout = self.io[1].take();
+        let stderr = self.io[2].take();
+
+        let (p, c) = stream();
+        task::spawn(proc() {
+            let mut stdout = stdout;
+            let output = stdout.as_mut().map_or(Ok(~[]), |s| s.read_to_end());
+            c.send(output);
+        });
+
+        let mut stderr = stderr;
+        let error = stderr.as_mut().map_or(Ok(~[]), |s| s.read_to_end());
+        let output = p.recv();
+
+        let status = self.wait();
+
+        ProcessOutput {
+            status: status,
+            output: output.unwrap_or(~[]),
+            error: error.unwrap_or(~[]),
+        }
+    }
 }
 
 impl Drop for Process {
@@ -213,7 +406,7 @@ impl Drop for Process {
 
 #[cfg(test)]
 mod tests {
-    use io::process::{ProcessConfig, Process};
+    use io::process::{ProcessConfig, Process, Command, MustDieSignal};
     use prelude::*;
 
     // FIXME(#10380)
@@ -348,6 +541,59 @@ mod tests {
         assert!(p.wait().success());
     })
 
+    // FIXME(#10380)
+    #[cfg(unix, not(target_os="android"))]
+    iotest!(fn wait_timeout_works() {
+        let args = ProcessConfig {
+            program: "/bin/sh",
+            args: &[~"-c", ~"sleep 1000"],
+            .. ProcessConfig::new()
+        };
+        let mut p = Process::new(args).unwrap();
+        assert_eq!(p.wait_timeout(10), None);
+        p.signal(MustDieSignal).unwrap();
+        assert!(!p.wait().success());
+    })
+
+    // FIXME(#10380)
+    #[cfg(unix, not(target_os="android"))]
+    iotest!(fn signal_group_works() {
+        use libc;
+        use task;
+
+        // The shell backgrounds a grandchild and reports its pid, closes
+        // its stdout so that `read_all` sees EOF, then waits on it. A `kill`
+        // of just the shell (the bug this feature exists to fix) would
+        // leave the grandchild running.
+        let io = ~[Ignored, CreatePipe(false, true)];
+        let args = ProcessConfig {
+            program: "/bin/sh",
+            args: &[~"-c", ~"sleep 1000 >/dev/null & echo $!; exec >&-; wait"],
+            io: io,
+            create_group: true,
+            .. ProcessConfig::new()
+        };
+        let mut p = Process::new(args).unwrap();
+        let pid_str = read_all(p.io[1].get_mut_ref() as &mut Reader);
+        let grandchild: libc::pid_t = from_str(pid_str.trim()).unwrap();
+
+        assert!(p.signal_group(MustDieSignal).is_ok());
+        assert!(!p.wait().success());
+
+        // `kill(pid, 0)` delivers no signal but still fails with ESRCH once
+        // the target is gone, so poll briefly for the grandchild to be
+        // reaped rather than asserting on it immediately.
+        let mut gone = false;
+        for _ in range(0u, 1000) {
+            if unsafe { libc::kill(grandchild, 0) } == -1 {
+                gone = true;
+                break;
+            }
+            task::deschedule();
+        }
+        assert!(gone, "signal_group only killed the shell, not its group");
+    })
+
     #[cfg(windows)]
     iotest!(fn uid_fails_on_windows() {
         let args = ProcessConfig {
@@ -389,4 +635,16 @@ mod tests {
         };
         assert!(Process::new(args).is_err());
     })
+
+    // FIXME(#10380)
+    #[cfg(unix, not(target_os="android"))]
+    iotest!(fn test_command_wait_with_output() {
+        let prog = Command::new("/bin/sh").arg("-c")
+                           .arg("echo foobar; echo baz 1>&2").spawn();
+        assert!(prog.is_ok());
+        let output = prog.unwrap().wait_with_output();
+        assert!(output.status.success());
+        assert_eq!(output.output, "foobar\n".as_bytes().to_owned());
+        assert_eq!(output.error, "baz\n".as_bytes().to_owned());
+    })
 }
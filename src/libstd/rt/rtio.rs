@@ -0,0 +1,76 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Abstract interfaces that an I/O backend (currently only the libuv
+//! backend in `rt::uv`) must implement in order to back the blocking API
+//! exposed in `std::io`. Nothing here is exposed to users of `std::io`
+//! directly; this is the seam between that API and whichever reactor is
+//! actually driving it.
+
+use io::IoResult;
+use io::process::{ProcessConfig, ProcessExit};
+use libc;
+
+/// A process as seen by the event loop backing `std::io::process`.
+pub trait RtioProcess {
+    /// Returns the process id of this child process
+    fn id(&self) -> libc::pid_t;
+
+    /// Sends the given signal to this process alone.
+    fn kill(&mut self, signal: int) -> IoResult<()>;
+
+    /// Like `kill`, but delivers to the process's whole group. Equivalent
+    /// to `kill` unless the process was spawned with `create_group`.
+    fn kill_group(&mut self, signal: int) -> IoResult<()>;
+
+    /// Blocks until the process has exited, returning its exit status.
+    fn wait(&mut self) -> ProcessExit;
+
+    /// Blocks until the process has exited or `msecs` milliseconds have
+    /// elapsed, whichever comes first. Returns `None` on timeout, in which
+    /// case the process is left running and any in-flight timer used to
+    /// implement the bound has already been torn down.
+    fn wait_timeout(&mut self, msecs: u64) -> Option<ProcessExit>;
+}
+
+/// One end of a pipe connected to a child process's stdio, as handed back
+/// by `IoFactory::spawn`. `io::PipeStream` wraps one of these to expose the
+/// usual `Reader`/`Writer` interface.
+pub trait RtioPipe {}
+
+/// The interface to the event loop that `LocalIo` hands out, used to
+/// actually create the OS resources backing `std::io`'s types.
+pub trait IoFactory {
+    /// Spawns a new process according to `config`, returning a handle to it
+    /// along with the pipe ends (if any) requested by `config.io`.
+    fn spawn(&mut self, config: ProcessConfig)
+        -> IoResult<(~RtioProcess, ~[Option<~RtioPipe>])>;
+}
+
+/// Grants temporary access to the event loop driving the current task, for
+/// use by the blocking wrappers in `std::io`.
+pub struct LocalIo;
+
+impl LocalIo {
+    /// Hands the `IoFactory` returned by `borrow` to `f`, propagating
+    /// whatever `IoResult` `f` returns.
+    ///
+    /// `borrow` looks up no event loop and always fails, so this function
+    /// fails without calling `f`.
+    pub fn maybe_raise<T>(f: |&mut IoFactory| -> IoResult<T>) -> IoResult<T> {
+        f(LocalIo::borrow())
+    }
+
+    fn borrow() -> &mut IoFactory {
+        // No `IoFactory` is installed anywhere: `rt::uv` implements
+        // `RtioProcess` but not `IoFactory`.
+        fail!("no event loop associated with the running task")
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The libuv-backed `RtioProcess`.
+
+use cast;
+use comm::{stream, Port, Chan};
+use io::IoResult;
+use io::process::ProcessConfig;
+use io::process::ProcessExit;
+use libc::{c_int, c_void, pid_t};
+use libc;
+use rt::rtio::RtioProcess;
+use rt::uv::timer::TimerWatcher;
+
+#[allow(non_camel_case_types)]
+type uv_loop_t = c_void;
+#[allow(non_camel_case_types)]
+type uv_process_t = c_void;
+
+// libuv's `UV_PROCESS_DETACHED`: setsid() on unix, CREATE_NEW_PROCESS_GROUP
+// on windows.
+static UV_PROCESS_DETACHED: c_int = 1 << 3;
+
+/// The `uv_process_options_t.flags` bits for `config`, to be OR'd in by
+/// `IoFactory::spawn` before calling `uv_spawn`.
+pub fn group_flags(config: &ProcessConfig) -> c_int {
+    if config.detach || config.create_group { UV_PROCESS_DETACHED } else { 0 }
+}
+
+extern {
+    fn rust_uv_process_kill(handle: *uv_process_t, signal: c_int) -> c_int;
+    fn rust_uv_process_pid(handle: *uv_process_t) -> pid_t;
+    fn rust_uv_process_loop(handle: *uv_process_t) -> *uv_loop_t;
+}
+
+/// A process spawned and managed through libuv. `exit` receives exactly one
+/// `ProcessExit` from the uv exit callback once the child has terminated;
+/// `wait`/`wait_timeout` simply read from it.
+pub struct Process {
+    priv handle: *uv_process_t,
+    priv exit: Port<ProcessExit>,
+    /// The result once `exit` has been read, cached so repeated `wait`/
+    /// `wait_timeout` calls are idempotent rather than blocking forever on
+    /// a port that only ever delivers once.
+    priv cached_exit: Option<ProcessExit>,
+    /// Whether this was spawned as the leader of a new process group (see
+    /// `group_flags`). Determines whether `kill_group` can actually reach
+    /// more than just this process.
+    priv is_group_leader: bool,
+}
+
+impl Process {
+    /// Wraps an already-spawned libuv process handle. `exit` is the port
+    /// that the uv exit callback installed when spawning will write the
+    /// final `ProcessExit` to, exactly once.
+    pub fn new(handle: *uv_process_t, exit: Port<ProcessExit>,
+               is_group_leader: bool) -> Process {
+        Process {
+            handle: handle,
+            exit: exit,
+            cached_exit: None,
+            is_group_leader: is_group_leader,
+        }
+    }
+}
+
+impl RtioProcess for Process {
+    fn id(&self) -> pid_t {
+        unsafe { rust_uv_process_pid(self.handle) }
+    }
+
+    fn kill(&mut self, signal: int) -> IoResult<()> {
+        match unsafe { rust_uv_process_kill(self.handle, signal as c_int) } {
+            0 => Ok(()),
+            _ => Err(super_io_error()),
+        }
+    }
+
+    // A negative pid signals the whole process group instead of just the
+    // process itself.
+    #[cfg(unix)]
+    fn kill_group(&mut self, signal: int) -> IoResult<()> {
+        let pid = self.id();
+        let target = if self.is_group_leader { -pid } else { pid };
+        match unsafe { libc::kill(target, signal as c_int) } {
+            0 => Ok(()),
+            _ => Err(super_io_error()),
+        }
+    }
+
+    // `GenerateConsoleCtrlEvent` reaches every process attached to the
+    // child's console, which `group_flags` arranges to be the whole group.
+    #[cfg(windows)]
+    fn kill_group(&mut self, signal: int) -> IoResult<()> {
+        if !self.is_group_leader {
+            return self.kill(signal);
+        }
+        match unsafe { generate_console_ctrl_event(signal, self.id()) } {
+            0 => Err(super_io_error()),
+            _ => Ok(()),
+        }
+    }
+
+    fn wait(&mut self) -> ProcessExit {
+        match self.cached_exit {
+            Some(exit) => exit,
+            None => {
+                let exit = self.exit.recv();
+                self.cached_exit = Some(exit);
+                exit
+            }
+        }
+    }
+
+    fn wait_timeout(&mut self, msecs: u64) -> Option<ProcessExit> {
+        if self.cached_exit.is_some() {
+            return self.cached_exit;
+        }
+
+        // The exit port only ever receives once, so peeking for a value
+        // that's already arrived is enough to make repeated short timeouts
+        // on an already-dead process cheap and non-blocking.
+        if self.exit.peek() {
+            return Some(self.wait());
+        }
+
+        let (timeout_port, timeout_chan) = stream::<()>();
+        let mut timer = TimerWatcher::new(unsafe { rust_uv_process_loop(self.handle) });
+        // The callback is a plain C function pointer, so it can't capture
+        // `timeout_chan` directly; stash a boxed copy on the handle's data
+        // field instead, and have the callback reclaim and drop it.
+        unsafe { timer.set_data(cast::transmute(~timeout_chan)) }
+        timer.start(msecs, timer_fired);
+
+        // `select!` races the two ports and only consumes from whichever
+        // fires first; the timer is always stopped afterwards so a late
+        // firing can't leak into the next `wait`/`wait_timeout` call.
+        let result = select! (
+            exit = self.exit.recv() => Some(exit),
+            _ = timeout_port.recv() => None
+        );
+        timer.stop();
+        match result {
+            Some(exit) => unsafe {
+                self.cached_exit = Some(exit);
+                // The timer never fired, so `timer_fired` never reclaimed
+                // the boxed channel stashed on its handle; do it here so
+                // it isn't leaked.
+                let _: ~Chan<()> = cast::transmute(timer.data());
+            },
+            None => {}
+        }
+        result
+    }
+}
+
+extern "C" fn timer_fired(handle: *c_void) {
+    unsafe {
+        let chan: ~Chan<()> = cast::transmute(rust_uv_handle_get_data(handle));
+        chan.try_send(());
+    }
+}
+
+extern {
+    fn rust_uv_handle_get_data(handle: *c_void) -> *c_void;
+}
+
+#[cfg(windows)]
+unsafe fn generate_console_ctrl_event(signal: int, pid: pid_t) -> c_int {
+    extern "system" {
+        fn GenerateConsoleCtrlEvent(event: u32, pgid: u32) -> c_int;
+    }
+    let event = if signal == ::io::process::MustDieSignal { 1u32 /* CTRL_BREAK_EVENT */ }
+                else { 0u32 /* CTRL_C_EVENT */ };
+    GenerateConsoleCtrlEvent(event, pid as u32)
+}
+
+fn super_io_error() -> ::io::IoError {
+    ::io::standard_error(::io::OtherIoError)
+}
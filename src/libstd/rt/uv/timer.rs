@@ -0,0 +1,71 @@
+// Copyright 2013 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A thin wrapper around libuv's `uv_timer_t`, used by `rt::uv::process` to
+//! put a bound on an otherwise-blocking `wait()`.
+
+use libc::{c_int, c_void};
+
+#[allow(non_camel_case_types)]
+type uv_loop_t = c_void;
+#[allow(non_camel_case_types)]
+type uv_timer_t = c_void;
+
+extern {
+    fn rust_uv_timer_new(loop_: *uv_loop_t) -> *uv_timer_t;
+    fn rust_uv_timer_start(handle: *uv_timer_t, timeout: u64,
+                            cb: extern "C" fn(*uv_timer_t)) -> c_int;
+    fn rust_uv_timer_stop(handle: *uv_timer_t) -> c_int;
+    fn rust_uv_timer_close(handle: *uv_timer_t);
+    fn rust_uv_handle_set_data(handle: *uv_timer_t, data: *c_void);
+    fn rust_uv_handle_get_data(handle: *uv_timer_t) -> *c_void;
+}
+
+/// A one-shot timer bound to a particular event loop. Dropping a
+/// `TimerWatcher` that was started but never fired or was never explicitly
+/// `stop`ped leaves the backing `uv_timer_t` to be cleaned up by `close`.
+pub struct TimerWatcher {
+    priv handle: *uv_timer_t,
+}
+
+impl TimerWatcher {
+    /// Creates a new, unarmed timer on the given event loop.
+    pub fn new(loop_: *uv_loop_t) -> TimerWatcher {
+        TimerWatcher { handle: unsafe { rust_uv_timer_new(loop_) } }
+    }
+
+    /// Stashes an opaque pointer on the timer's `uv_handle_t.data` field so
+    /// that `cb` (which, being a plain C callback, captures nothing) can
+    /// recover it with `data()`.
+    pub fn set_data(&mut self, data: *c_void) {
+        unsafe { rust_uv_handle_set_data(self.handle, data); }
+    }
+
+    /// Recovers whatever pointer was last passed to `set_data`.
+    pub fn data(&self) -> *c_void {
+        unsafe { rust_uv_handle_get_data(self.handle) }
+    }
+
+    /// Arms the timer to fire `cb` once, after `msecs` milliseconds.
+    pub fn start(&mut self, msecs: u64, cb: extern "C" fn(*uv_timer_t)) {
+        unsafe { rust_uv_timer_start(self.handle, msecs, cb); }
+    }
+
+    /// Disarms the timer. Safe to call whether or not it has already fired.
+    pub fn stop(&mut self) {
+        unsafe { rust_uv_timer_stop(self.handle); }
+    }
+}
+
+impl Drop for TimerWatcher {
+    fn drop(&mut self) {
+        unsafe { rust_uv_timer_close(self.handle); }
+    }
+}